@@ -1,24 +1,109 @@
+use std::borrow::Cow;
 use std::fmt;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
 pub type Context<'a> = (&'a str, &'a str);
 
-pub type SourceLoc = (usize, usize, usize);
+/// A location in the source buffer. Deliberately excluded from structural
+/// equality (see the `PartialEq` impl below) so that `Expr`/`Token` values
+/// parsed from differently-positioned text can still compare equal.
+///
+/// `width` is the number of columns the spanned token occupies, so a caret
+/// rendering (see `ParseError`'s `Debug` impl) can underline the whole
+/// token rather than just its first character.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+    pub line_start: usize,
+    pub width: usize,
+}
+
+impl PartialEq for Span {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct Expr<'a>(ExprType<'a>, SourceLoc);
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
+pub struct Expr<'a>(ExprType<'a>, Span);
 
 #[derive(Debug, Clone, PartialEq)]
-pub struct Token<'a>(TokenType<'a>, SourceLoc);
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
+pub struct Token<'a>(TokenType<'a>, Span);
+
+impl<'a> Expr<'a> {
+    pub fn new(kind: ExprType<'a>, loc: Span) -> Self {
+        Expr(kind, loc)
+    }
+
+    pub fn kind(&self) -> &ExprType<'a> {
+        &self.0
+    }
+
+    pub fn loc(&self) -> Span {
+        self.1
+    }
+
+    /// Same as `==`, spelled out for call sites where the span-insensitivity
+    /// is load-bearing rather than incidental (e.g. parser tests).
+    pub fn eq_ignore_span(&self, other: &Self) -> bool {
+        self == other
+    }
+
+    /// Consuming counterpart to `kind()`/`loc()`: hands back the owned
+    /// `ExprType` and `Span` without cloning. Used by `walk_expr_mut`, which
+    /// already owns `e` by value and only needs to move its fields out to
+    /// match on them, not borrow-then-clone the whole subtree.
+    pub(crate) fn into_parts(self) -> (ExprType<'a>, Span) {
+        (self.0, self.1)
+    }
+}
+
+impl<'a> Token<'a> {
+    pub fn new(kind: TokenType<'a>, loc: Span) -> Self {
+        Token(kind, loc)
+    }
+
+    pub fn kind(&self) -> &TokenType<'a> {
+        &self.0
+    }
+
+    pub fn loc(&self) -> Span {
+        self.1
+    }
+
+    pub fn eq_ignore_span(&self, other: &Self) -> bool {
+        self == other
+    }
+}
 
 #[derive(Clone, PartialEq)]
-pub struct ParseError<'a>(Error, Context<'a>, SourceLoc);
+pub struct ParseError<'a>(Error<'a>, Context<'a>, Span);
 
+/// A batch of errors collected by a multi-error parse pass, rather than
+/// bailing out on the first one.
 #[derive(Clone, PartialEq)]
-pub enum Error {
+pub struct ParseErrors<'a>(pub Vec<ParseError<'a>>);
+
+#[derive(Clone, PartialEq)]
+pub enum Error<'a> {
     UnterminatedString,
+    UnexpectedToken,
+    ExpectedToken(TokenType<'a>),
+    UnknownCharacter(char),
+    MismatchedDelimiter,
+    InvalidNumber,
 }
 
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Keyword {
     Fun,
     Pub,
@@ -36,6 +121,7 @@ pub enum Keyword {
 }
 
 #[derive(Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Operator {
     Add,
     Sub,
@@ -61,17 +147,21 @@ pub enum Operator {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub enum Type<'a> {
     Byte, //u8
     Int,  //i32
     Long, //i64
     Float, //f32
     Double, //f64
-    Class(&'a str),
-    Generic(&'a str, Vec<Type<'a>>),
+    Class(Cow<'a, str>),
+    Generic(Cow<'a, str>, Vec<Type<'a>>),
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub enum TokenType<'a> {
     Dot,
     Semi,
@@ -86,24 +176,30 @@ pub enum TokenType<'a> {
     RCurly,
     Int(u64),
     Float(f64),
-    Id(&'a str),
+    Id(Cow<'a, str>),
     Kw(Keyword),
-    Str(&'a str),
+    Str(Cow<'a, str>),
     Op(Operator, bool),
 }
 
+// Name/text fields use `Cow<'a, str>` rather than `&'a str`: JSON strings
+// containing an escape (`\\`, `\"`, a control character) can't be
+// deserialized as a zero-copy borrow, so `from_json` needs the option to
+// allocate an owned `String` instead.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'a")))]
 pub enum ExprType<'a> {
     EInt(u64),
     EFloat(f64),
-    EId(&'a str),
-    EString(&'a str),
+    EId(Cow<'a, str>),
+    EString(Cow<'a, str>),
     EArray(Vec<Expr<'a>>),
     EUnop(Operator, Box<Expr<'a>>),
     EBinop(Operator, Box<(Expr<'a>, Expr<'a>)>),
-    EVar(Type<'a>, Vec<(&'a str, Option<Expr<'a>>)>),
-    EFunc(&'a str, i32, Vec<Type<'a>>, Vec<Expr<'a>>),
-    EClass(&'a str, i32, Vec<Type<'a>>, Vec<Expr<'a>>),
+    EVar(Type<'a>, Vec<(Cow<'a, str>, Option<Expr<'a>>)>),
+    EFunc(Cow<'a, str>, i32, Vec<Type<'a>>, Vec<Expr<'a>>),
+    EClass(Cow<'a, str>, i32, Vec<Type<'a>>, Vec<Expr<'a>>),
     EIf(Vec<(Expr<'a>, Vec<Expr<'a>>)>, Option<Vec<Expr<'a>>>),
 }
 
@@ -115,7 +211,7 @@ impl<'a> From<&'a str> for Type<'a> {
             "long" => Type::Long,
             "float" => Type::Float,
             "double" => Type::Double,
-            _ => Type::Class(type_name),
+            _ => Type::Class(Cow::Borrowed(type_name)),
         }
     }
 }
@@ -124,19 +220,43 @@ impl<'a> fmt::Debug for ParseError<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use self::Error::*;
 
-        let ParseError(error, context, source_loc) = self;
-        let (line, column, line_start) = source_loc;
+        let ParseError(error, context, span) = self;
+        let Span { line, column, line_start, width } = span;
         let (context, source) = context;
 
+        let source_line = unsafe {
+            source
+                .get_unchecked(*line_start..)
+                .lines().next().unwrap_or("EOF")
+        };
+
         write!(f, "Error on {}:{}:{}> ", context, line, column)
             .and_then(|_| match error {
-                UnterminatedString => write!(f, "Untermiated string literal"),
+                UnterminatedString => write!(f, "Unterminated string literal"),
+                UnexpectedToken => write!(f, "Unexpected token"),
+                ExpectedToken(expected) => write!(f, "Expected {:?}", expected),
+                UnknownCharacter(c) => write!(f, "Unknown character {:?}", c),
+                MismatchedDelimiter => write!(f, "Mismatched delimiter"),
+                InvalidNumber => write!(f, "Invalid number literal"),
+            })
+            .and_then(|_| {
+                write!(
+                    f,
+                    "\n  {}\n  {}{}",
+                    source_line,
+                    " ".repeat(column.saturating_sub(1)),
+                    "^".repeat((*width).max(1))
+                )
             })
-            .and_then(|_| write!(f, "\n  {}", unsafe {
-                source
-                    .get_unchecked(*line_start..)
-                    .lines().next().unwrap_or("EOF")
-            }))
+    }
+}
+
+impl<'a> fmt::Debug for ParseErrors<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for error in &self.0 {
+            writeln!(f, "{:?}", error)?;
+        }
+        Ok(())
     }
 }
 
@@ -162,6 +282,41 @@ impl fmt::Debug for Keyword {
     }
 }
 
+impl Operator {
+    /// Binding power used to decide when a nested `EBinop` needs parens
+    /// around it when printed. Higher binds tighter.
+    pub fn precedence(&self) -> u8 {
+        use self::Operator::*;
+
+        match self {
+            Not | BitNot => 11,
+            Mul | Div | Mod => 10,
+            Add | Sub => 9,
+            Shl | Shr => 8,
+            Equ | Neq | Lt | Lte | Gt | Gte => 7,
+            BitAnd => 6,
+            Xor => 5,
+            BitOr => 4,
+            And => 3,
+            Or => 2,
+            Set => 1,
+        }
+    }
+
+    pub fn is_right_assoc(&self) -> bool {
+        matches!(self, Operator::Set)
+    }
+
+    /// Binding power of this operator used as a unary prefix (`EUnop`).
+    /// Unlike `precedence`, this always outranks every binary operator:
+    /// unary `-` applies to a single operand no matter how loosely `-`
+    /// binds as a binary operator, so `-(a * b)` must not print as
+    /// `-a * b` (which would reparse as `(-a) * b`).
+    pub fn unary_precedence(&self) -> u8 {
+        12
+    }
+}
+
 impl fmt::Debug for Operator {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         use self::Operator::*;
@@ -190,4 +345,51 @@ impl fmt::Debug for Operator {
             BitNot => "~",
         })
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caret_underlines_the_full_token_width() {
+        let err = ParseError(
+            Error::UnknownCharacter('@'),
+            ("test.clw", "x = @ + 1"),
+            Span { line: 1, column: 5, line_start: 0, width: 1 },
+        );
+
+        assert_eq!(
+            format!("{:?}", err),
+            "Error on test.clw:1:5> Unknown character '@'\n  x = @ + 1\n      ^"
+        );
+    }
+
+    #[test]
+    fn caret_underlines_multi_column_tokens() {
+        let err = ParseError(
+            Error::ExpectedToken(TokenType::Semi),
+            ("test.clw", "int x = 42"),
+            Span { line: 1, column: 9, line_start: 0, width: 2 },
+        );
+
+        assert_eq!(
+            format!("{:?}", err),
+            "Error on test.clw:1:9> Expected Semi\n  int x = 42\n          ^^"
+        );
+    }
+
+    #[test]
+    fn parse_errors_renders_each_error_as_its_own_three_line_block() {
+        let loc = Span { line: 1, column: 1, line_start: 0, width: 1 };
+        let errors = ParseErrors(vec![
+            ParseError(Error::UnterminatedString, ("a.clw", "\"abc"), loc),
+            ParseError(Error::MismatchedDelimiter, ("a.clw", "(abc"), loc),
+        ]);
+
+        let rendered = format!("{:?}", errors);
+        assert_eq!(rendered.lines().count(), 6);
+        assert!(rendered.contains("Unterminated string literal"));
+        assert!(rendered.contains("Mismatched delimiter"));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,222 @@
+//! String interning so identifiers and type names become cheap `Symbol` ids.
+//!
+//! Borrowed from rustc's `Name`/`Symbol` interner: a `Symbol` is a `Copy`
+//! integer handle into an `Interner`, so equality and hashing become an
+//! integer compare instead of a string compare. `Expr`/`Type` hold their
+//! names as `Cow<'a, str>`, usually borrowed from the source buffer, which
+//! is fine while parsing but keeps the tree tied to the buffer's lifetime; `SExpr`/`SType` are the `'static`
+//! counterparts produced by interning a borrowed tree, for callers (caches,
+//! language servers) that need to keep the AST around after the source text
+//! is gone.
+
+use std::collections::HashMap;
+
+use super::ast::{Expr, ExprType, Operator, Type};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Symbol(u32);
+
+#[derive(Debug, Default)]
+pub struct Interner {
+    names: HashMap<String, u32>,
+    strings: Vec<String>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Interner::default()
+    }
+
+    pub fn intern(&mut self, string: &str) -> Symbol {
+        if let Some(&id) = self.names.get(string) {
+            return Symbol(id);
+        }
+
+        let id = self.strings.len() as u32;
+        self.strings.push(string.to_string());
+        self.names.insert(string.to_string(), id);
+        Symbol(id)
+    }
+
+    pub fn resolve(&self, symbol: Symbol) -> &str {
+        &self.strings[symbol.0 as usize]
+    }
+
+    /// Intern every identifier/type-name in a `Type`, producing its
+    /// `'static` counterpart.
+    pub fn intern_type(&mut self, ty: &Type) -> SType {
+        match ty {
+            Type::Byte => SType::Byte,
+            Type::Int => SType::Int,
+            Type::Long => SType::Long,
+            Type::Float => SType::Float,
+            Type::Double => SType::Double,
+            Type::Class(name) => SType::Class(self.intern(name)),
+            Type::Generic(name, args) => SType::Generic(
+                self.intern(name),
+                args.iter().map(|arg| self.intern_type(arg)).collect(),
+            ),
+        }
+    }
+
+    /// Intern every identifier/type-name in an `Expr`, producing its
+    /// `'static` counterpart.
+    pub fn intern_expr(&mut self, expr: &Expr) -> SExpr {
+        match expr.kind() {
+            ExprType::EInt(n) => SExpr::EInt(*n),
+            ExprType::EFloat(n) => SExpr::EFloat(*n),
+            ExprType::EId(name) => SExpr::EId(self.intern(name)),
+            ExprType::EString(s) => SExpr::EString(self.intern(s)),
+            ExprType::EArray(items) => {
+                SExpr::EArray(items.iter().map(|item| self.intern_expr(item)).collect())
+            }
+            ExprType::EUnop(op, operand) => {
+                SExpr::EUnop(op.clone(), Box::new(self.intern_expr(operand)))
+            }
+            ExprType::EBinop(op, operands) => SExpr::EBinop(
+                op.clone(),
+                Box::new((self.intern_expr(&operands.0), self.intern_expr(&operands.1))),
+            ),
+            ExprType::EVar(ty, bindings) => SExpr::EVar(
+                self.intern_type(ty),
+                bindings
+                    .iter()
+                    .map(|(name, init)| {
+                        (
+                            self.intern(name),
+                            init.as_ref().map(|init| self.intern_expr(init)),
+                        )
+                    })
+                    .collect(),
+            ),
+            ExprType::EFunc(name, flags, params, body) => SExpr::EFunc(
+                self.intern(name),
+                *flags,
+                params.iter().map(|p| self.intern_type(p)).collect(),
+                body.iter().map(|stmt| self.intern_expr(stmt)).collect(),
+            ),
+            ExprType::EClass(name, flags, fields, body) => SExpr::EClass(
+                self.intern(name),
+                *flags,
+                fields.iter().map(|f| self.intern_type(f)).collect(),
+                body.iter().map(|stmt| self.intern_expr(stmt)).collect(),
+            ),
+            ExprType::EIf(branches, else_block) => SExpr::EIf(
+                branches
+                    .iter()
+                    .map(|(cond, block)| {
+                        (
+                            self.intern_expr(cond),
+                            block.iter().map(|stmt| self.intern_expr(stmt)).collect(),
+                        )
+                    })
+                    .collect(),
+                else_block
+                    .as_ref()
+                    .map(|block| block.iter().map(|stmt| self.intern_expr(stmt)).collect()),
+            ),
+        }
+    }
+}
+
+/// `'static` counterpart of `Type`, with every name replaced by a `Symbol`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SType {
+    Byte,
+    Int,
+    Long,
+    Float,
+    Double,
+    Class(Symbol),
+    Generic(Symbol, Vec<SType>),
+}
+
+/// `'static` counterpart of `ExprType`, with every name replaced by a `Symbol`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SExpr {
+    EInt(u64),
+    EFloat(f64),
+    EId(Symbol),
+    EString(Symbol),
+    EArray(Vec<SExpr>),
+    EUnop(Operator, Box<SExpr>),
+    EBinop(Operator, Box<(SExpr, SExpr)>),
+    EVar(SType, Vec<(Symbol, Option<SExpr>)>),
+    EFunc(Symbol, i32, Vec<SType>, Vec<SExpr>),
+    EClass(Symbol, i32, Vec<SType>, Vec<SExpr>),
+    EIf(Vec<(SExpr, Vec<SExpr>)>, Option<Vec<SExpr>>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::ast::Span;
+
+    const LOC: Span = Span { line: 1, column: 1, line_start: 0, width: 1 };
+
+    #[test]
+    fn interning_the_same_string_twice_returns_the_same_symbol() {
+        let mut interner = Interner::new();
+        let a = interner.intern("hello");
+        let b = interner.intern("hello");
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn resolve_round_trips_an_interned_name() {
+        let mut interner = Interner::new();
+        let symbol = interner.intern("world");
+        assert_eq!(interner.resolve(symbol), "world");
+    }
+
+    #[test]
+    fn distinct_strings_intern_to_distinct_symbols() {
+        let mut interner = Interner::new();
+        let a = interner.intern("a");
+        let b = interner.intern("b");
+        assert_ne!(a, b);
+    }
+
+    // EIf nests conditions and two separate statement lists (the branch
+    // block and the else block), which is exactly the shape a copy-paste
+    // slip between match arms (e.g. interning the condition twice, or
+    // dropping the else block) would silently get wrong.
+    #[test]
+    fn intern_expr_covers_nested_if_branches_and_else() {
+        let cond = Expr::new(ExprType::EId("flag".into()), LOC);
+        let then_stmt = Expr::new(ExprType::EId("then_branch".into()), LOC);
+        let else_stmt = Expr::new(ExprType::EId("else_branch".into()), LOC);
+        let expr = Expr::new(
+            ExprType::EIf(
+                vec![(cond, vec![then_stmt])],
+                Some(vec![else_stmt]),
+            ),
+            LOC,
+        );
+
+        let mut interner = Interner::new();
+        let sexpr = interner.intern_expr(&expr);
+
+        match sexpr {
+            SExpr::EIf(branches, else_block) => {
+                assert_eq!(branches.len(), 1);
+                let (cond, block) = &branches[0];
+                assert_eq!(interner.resolve(expect_id(cond)), "flag");
+                assert_eq!(block.len(), 1);
+                assert_eq!(interner.resolve(expect_id(&block[0])), "then_branch");
+
+                let else_block = else_block.expect("else block should be interned");
+                assert_eq!(else_block.len(), 1);
+                assert_eq!(interner.resolve(expect_id(&else_block[0])), "else_branch");
+            }
+            other => panic!("expected SExpr::EIf, got {:?}", other),
+        }
+    }
+
+    fn expect_id(sexpr: &SExpr) -> Symbol {
+        match sexpr {
+            SExpr::EId(symbol) => *symbol,
+            other => panic!("expected SExpr::EId, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+//! JSON (de)serialization of the AST, behind the `serde` feature.
+//!
+//! Lets external tooling (language servers, formatters, other-language
+//! consumers) read a parsed tree as JSON and reconstruct it, mirroring how
+//! rustc's `libsyntax` AST implements `Encodable`/`Decodable`.
+
+use serde_json;
+
+use super::ast::Expr;
+
+pub fn to_json(expr: &Expr) -> String {
+    serde_json::to_string(expr).expect("Expr serialization is infallible")
+}
+
+pub fn from_json<'a>(json: &'a str) -> serde_json::Result<Expr<'a>> {
+    serde_json::from_str(json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::ast::{ExprType, Span};
+
+    const LOC: Span = Span { line: 1, column: 1, line_start: 0, width: 1 };
+
+    #[test]
+    fn round_trips_a_plain_identifier() {
+        let expr = Expr::new(ExprType::EId("x".into()), LOC);
+        let json = to_json(&expr);
+        assert_eq!(from_json(&json).unwrap(), expr);
+    }
+
+    #[test]
+    fn round_trips_a_string_that_needs_unescaping() {
+        // Backslashes and quotes force serde's string deserializer to
+        // allocate rather than borrow, which is exactly what `Cow<'a, str>`
+        // is for.
+        let expr = Expr::new(ExprType::EString("has a \\ backslash and a \" quote".into()), LOC);
+        let json = to_json(&expr);
+        assert_eq!(from_json(&json).unwrap(), expr);
+    }
+}
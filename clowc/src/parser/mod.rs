@@ -0,0 +1,8 @@
+pub mod ast;
+pub mod intern;
+#[cfg(feature = "serde")]
+pub mod json;
+pub mod print;
+#[macro_use]
+pub mod span;
+pub mod visit;
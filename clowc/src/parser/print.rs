@@ -0,0 +1,227 @@
+//! Pretty-printer that renders `Expr`/`Type` back into clowlang source text.
+//!
+//! Modeled on rustc's `libsyntax::print::pprust`: every node knows how to
+//! render itself, and the only state threaded through is indentation depth.
+//! Parser tests can use this to assert `parse(print(ast)) == ast`.
+
+use super::ast::{Expr, ExprType, Keyword, Type};
+
+const INDENT: &str = "    ";
+
+/// Render an `Expr` back into source text.
+pub fn print_expr(e: &Expr) -> String {
+    print_expr_at(e, 0)
+}
+
+/// Render a `Type` back into source text.
+pub fn print_type(t: &Type) -> String {
+    match t {
+        Type::Byte => "byte".to_string(),
+        Type::Int => "int".to_string(),
+        Type::Long => "long".to_string(),
+        Type::Float => "float".to_string(),
+        Type::Double => "double".to_string(),
+        Type::Class(name) => name.to_string(),
+        Type::Generic(name, args) => format!(
+            "{}<{}>",
+            name,
+            args.iter().map(print_type).collect::<Vec<_>>().join(", ")
+        ),
+    }
+}
+
+fn print_expr_at(e: &Expr, depth: usize) -> String {
+    match e.kind() {
+        ExprType::EInt(n) => n.to_string(),
+        ExprType::EFloat(n) => n.to_string(),
+        ExprType::EId(name) => name.to_string(),
+        ExprType::EString(s) => format!("\"{}\"", s),
+        ExprType::EArray(items) => format!(
+            "[{}]",
+            items
+                .iter()
+                .map(|item| print_expr_at(item, depth))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        ExprType::EUnop(op, operand) => format!(
+            "{:?}{}",
+            op,
+            // A unary operator binds to a single operand, so even an
+            // equal-precedence child must be parenthesized: `-(a - b)` must
+            // not print as `-a - b`, which would reparse as `(-a) - b`.
+            print_child(operand, op.unary_precedence(), true, depth)
+        ),
+        ExprType::EBinop(op, operands) => {
+            let (lhs, rhs) = operands.as_ref();
+            format!(
+                "{} {:?} {}",
+                print_child(lhs, op.precedence(), op.is_right_assoc(), depth),
+                op,
+                print_child(rhs, op.precedence(), !op.is_right_assoc(), depth)
+            )
+        }
+        ExprType::EVar(ty, bindings) => format!(
+            "{} {};",
+            print_type(ty),
+            bindings
+                .iter()
+                .map(|(name, init)| match init {
+                    Some(value) => format!("{} = {}", name, print_expr_at(value, depth)),
+                    None => name.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        ExprType::EFunc(name, _, params, body) => format!(
+            "{:?} {}({}) {{\n{}\n{}}}",
+            Keyword::Fun,
+            name,
+            params.iter().map(print_type).collect::<Vec<_>>().join(", "),
+            print_block(body, depth + 1),
+            indent(depth)
+        ),
+        ExprType::EClass(name, _, fields, body) => format!(
+            "{:?} {}({}) {{\n{}\n{}}}",
+            Keyword::Class,
+            name,
+            fields.iter().map(print_type).collect::<Vec<_>>().join(", "),
+            print_block(body, depth + 1),
+            indent(depth)
+        ),
+        ExprType::EIf(branches, else_block) => print_if(branches, else_block, depth),
+    }
+}
+
+/// Print a child operand, wrapping it in parens when its own precedence is
+/// too low to stand unparenthesized next to its parent.
+fn print_child(child: &Expr, parent_prec: u8, needs_eq_parens: bool, depth: usize) -> String {
+    let rendered = print_expr_at(child, depth);
+
+    let child_prec = match child.kind() {
+        ExprType::EBinop(op, _) => Some(op.precedence()),
+        ExprType::EUnop(op, _) => Some(op.unary_precedence()),
+        _ => None,
+    };
+
+    match child_prec {
+        Some(prec) if prec < parent_prec || (prec == parent_prec && needs_eq_parens) => {
+            format!("({})", rendered)
+        }
+        _ => rendered,
+    }
+}
+
+fn print_if(branches: &[(Expr, Vec<Expr>)], else_block: &Option<Vec<Expr>>, depth: usize) -> String {
+    let mut out = String::new();
+
+    for (i, (cond, block)) in branches.iter().enumerate() {
+        let keyword = if i == 0 { Keyword::If } else { Keyword::Elif };
+        out.push_str(&format!(
+            "{:?} {} {{\n{}\n{}}}",
+            keyword,
+            print_expr_at(cond, depth),
+            print_block(block, depth + 1),
+            indent(depth)
+        ));
+        out.push(' ');
+    }
+
+    if let Some(block) = else_block {
+        out.push_str(&format!(
+            "{:?} {{\n{}\n{}}}",
+            Keyword::Else,
+            print_block(block, depth + 1),
+            indent(depth)
+        ));
+    } else {
+        out.pop();
+    }
+
+    out
+}
+
+fn print_block(body: &[Expr], depth: usize) -> String {
+    body.iter()
+        .map(|stmt| format!("{}{}", indent(depth), print_expr_at(stmt, depth)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn indent(depth: usize) -> String {
+    INDENT.repeat(depth)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::ast::{Operator, Span};
+
+    const LOC: Span = Span { line: 1, column: 1, line_start: 0, width: 1 };
+
+    fn id(name: &str) -> Expr<'_> {
+        Expr::new(ExprType::EId(name.into()), LOC)
+    }
+
+    fn binop<'a>(op: Operator, lhs: Expr<'a>, rhs: Expr<'a>) -> Expr<'a> {
+        Expr::new(ExprType::EBinop(op, Box::new((lhs, rhs))), LOC)
+    }
+
+    fn unop(op: Operator, operand: Expr) -> Expr {
+        Expr::new(ExprType::EUnop(op, Box::new(operand)), LOC)
+    }
+
+    #[test]
+    fn prints_leaf_exprs() {
+        assert_eq!(print_expr(&Expr::new(ExprType::EInt(42), LOC)), "42");
+        assert_eq!(print_expr(&id("x")), "x");
+        assert_eq!(
+            print_expr(&Expr::new(ExprType::EString("hi".into()), LOC)),
+            "\"hi\""
+        );
+    }
+
+    #[test]
+    fn lower_precedence_child_gets_parens() {
+        // (a + b) * c
+        let expr = binop(Operator::Mul, binop(Operator::Add, id("a"), id("b")), id("c"));
+        assert_eq!(print_expr(&expr), "(a + b) * c");
+    }
+
+    #[test]
+    fn same_precedence_left_assoc_child_is_bare() {
+        // a - b - c, left child at equal precedence needs no parens
+        let expr = binop(Operator::Sub, binop(Operator::Sub, id("a"), id("b")), id("c"));
+        assert_eq!(print_expr(&expr), "a - b - c");
+    }
+
+    #[test]
+    fn same_precedence_right_child_of_left_assoc_op_gets_parens() {
+        // a - (b - c) must not print as "a - b - c"
+        let expr = binop(Operator::Sub, id("a"), binop(Operator::Sub, id("b"), id("c")));
+        assert_eq!(print_expr(&expr), "a - (b - c)");
+    }
+
+    #[test]
+    fn unop_over_equal_precedence_binop_gets_parens() {
+        // -(a - b) must not print as "-a - b", which would reparse as (-a) - b
+        let expr = unop(Operator::Sub, binop(Operator::Sub, id("a"), id("b")));
+        assert_eq!(print_expr(&expr), "-(a - b)");
+    }
+
+    #[test]
+    fn unop_over_higher_precedence_binop_still_gets_parens() {
+        // -(a * b) must not print as "-a * b", which would reparse as (-a) * b:
+        // unary `-` binds tighter than *every* binary operator, including Mul,
+        // which outranks Sub's own binary precedence.
+        let expr = unop(Operator::Sub, binop(Operator::Mul, id("a"), id("b")));
+        assert_eq!(print_expr(&expr), "-(a * b)");
+    }
+
+    #[test]
+    fn unop_child_of_binop_is_bare() {
+        // -a * b should stay unparenthesized: unary minus binds tightest.
+        let expr = binop(Operator::Mul, unop(Operator::Sub, id("a")), id("b"));
+        assert_eq!(print_expr(&expr), "-a * b");
+    }
+}
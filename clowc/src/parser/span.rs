@@ -0,0 +1,48 @@
+//! Test helper built on `Span`-insensitive equality.
+//!
+//! `Expr`/`Token` already ignore their `Span` when compared with `==` (see
+//! `ast::Span`'s `PartialEq` impl), so `eq_ignore_span` is just a clearly
+//! named spelling of that comparison. This macro wraps it the way
+//! `assert_eq!` wraps `==`, recursing through the whole tree and printing
+//! both sides' `{:?}` output on failure.
+
+#[macro_export]
+macro_rules! assert_eq_ignore_span {
+    ($left:expr, $right:expr $(,)?) => {
+        match (&$left, &$right) {
+            (left_val, right_val) => {
+                if !left_val.eq_ignore_span(right_val) {
+                    panic!(
+                        "assertion failed: `(left == right)` (ignoring spans)\n  left: `{:?}`\n right: `{:?}`",
+                        left_val, right_val
+                    );
+                }
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::ast::{Expr, ExprType, Span};
+
+    fn id_at(name: &str, column: usize) -> Expr<'_> {
+        Expr::new(ExprType::EId(name.into()), Span { line: 1, column, line_start: 0, width: 1 })
+    }
+
+    #[test]
+    fn exprs_parsed_at_different_columns_are_still_eq() {
+        let a = id_at("x", 1);
+        let b = id_at("x", 5);
+        assert_ne!(a.loc().column, b.loc().column);
+        assert_eq_ignore_span!(a, b);
+    }
+
+    #[test]
+    #[should_panic(expected = "assertion failed")]
+    fn differing_kinds_still_fail_despite_ignoring_span() {
+        let a = id_at("x", 1);
+        let b = id_at("y", 1);
+        assert_eq_ignore_span!(a, b);
+    }
+}
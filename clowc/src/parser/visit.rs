@@ -0,0 +1,261 @@
+//! Generic traversal over the `Expr` tree.
+//!
+//! Modeled on rustc's `visit`/`intravisit` split: `Visitor` gets a
+//! default-implemented `visit_*` for every node kind, and the free
+//! `walk_*` functions do the actual recursion into boxed children so a
+//! visitor only has to override the hooks it cares about. `Fold` is the
+//! mutable counterpart, rewriting nodes instead of just observing them.
+//! Passes like constant folding, identifier renaming, or type collection
+//! can be written once here instead of hand-rolling recursion per variant.
+
+use std::borrow::Cow;
+
+use super::ast::{Expr, ExprType, Operator, Type};
+
+pub trait Visitor<'a>: Sized {
+    fn visit_expr(&mut self, e: &Expr<'a>) {
+        walk_expr(self, e);
+    }
+
+    fn visit_type(&mut self, t: &Type<'a>) {
+        walk_type(self, t);
+    }
+
+    fn visit_operator(&mut self, _op: &Operator) {}
+
+    // Per-variant hooks, each defaulted to the structural recursion that
+    // `walk_expr` would otherwise inline. Overriding one of these lets a
+    // pass (e.g. renaming identifiers, or only caring about `EIf`) target a
+    // single node kind without re-deriving the rest of the traversal.
+    fn visit_int(&mut self, _n: u64) {}
+
+    fn visit_float(&mut self, _n: f64) {}
+
+    fn visit_id(&mut self, _name: &str) {}
+
+    fn visit_string(&mut self, _s: &str) {}
+
+    fn visit_array(&mut self, items: &[Expr<'a>]) {
+        for item in items {
+            self.visit_expr(item);
+        }
+    }
+
+    fn visit_unop(&mut self, op: &Operator, operand: &Expr<'a>) {
+        self.visit_operator(op);
+        self.visit_expr(operand);
+    }
+
+    fn visit_binop(&mut self, op: &Operator, lhs: &Expr<'a>, rhs: &Expr<'a>) {
+        self.visit_operator(op);
+        self.visit_expr(lhs);
+        self.visit_expr(rhs);
+    }
+
+    fn visit_var(&mut self, ty: &Type<'a>, bindings: &[(Cow<'a, str>, Option<Expr<'a>>)]) {
+        self.visit_type(ty);
+        for (_, init) in bindings {
+            if let Some(init) = init {
+                self.visit_expr(init);
+            }
+        }
+    }
+
+    fn visit_func(&mut self, _name: &str, _flags: i32, params: &[Type<'a>], body: &[Expr<'a>]) {
+        for param in params {
+            self.visit_type(param);
+        }
+        for stmt in body {
+            self.visit_expr(stmt);
+        }
+    }
+
+    fn visit_class(&mut self, _name: &str, _flags: i32, fields: &[Type<'a>], body: &[Expr<'a>]) {
+        for field in fields {
+            self.visit_type(field);
+        }
+        for stmt in body {
+            self.visit_expr(stmt);
+        }
+    }
+
+    fn visit_if(&mut self, branches: &[(Expr<'a>, Vec<Expr<'a>>)], else_block: &Option<Vec<Expr<'a>>>) {
+        for (cond, block) in branches {
+            self.visit_expr(cond);
+            for stmt in block {
+                self.visit_expr(stmt);
+            }
+        }
+        if let Some(block) = else_block {
+            for stmt in block {
+                self.visit_expr(stmt);
+            }
+        }
+    }
+}
+
+pub fn walk_expr<'a, V: Visitor<'a>>(visitor: &mut V, e: &Expr<'a>) {
+    match e.kind() {
+        ExprType::EInt(n) => visitor.visit_int(*n),
+        ExprType::EFloat(n) => visitor.visit_float(*n),
+        ExprType::EId(name) => visitor.visit_id(name),
+        ExprType::EString(s) => visitor.visit_string(s),
+        ExprType::EArray(items) => visitor.visit_array(items),
+        ExprType::EUnop(op, operand) => visitor.visit_unop(op, operand),
+        ExprType::EBinop(op, operands) => visitor.visit_binop(op, &operands.0, &operands.1),
+        ExprType::EVar(ty, bindings) => visitor.visit_var(ty, bindings),
+        ExprType::EFunc(name, flags, params, body) => visitor.visit_func(name, *flags, params, body),
+        ExprType::EClass(name, flags, fields, body) => visitor.visit_class(name, *flags, fields, body),
+        ExprType::EIf(branches, else_block) => visitor.visit_if(branches, else_block),
+    }
+}
+
+pub fn walk_type<'a, V: Visitor<'a>>(visitor: &mut V, t: &Type<'a>) {
+    if let Type::Generic(_, args) = t {
+        for arg in args {
+            visitor.visit_type(arg);
+        }
+    }
+}
+
+/// Mutable counterpart to `Visitor`: rewrites a tree instead of observing it.
+pub trait Fold<'a>: Sized {
+    fn fold_expr(&mut self, e: Expr<'a>) -> Expr<'a> {
+        walk_expr_mut(self, e)
+    }
+
+    fn fold_type(&mut self, t: Type<'a>) -> Type<'a> {
+        walk_type_mut(self, t)
+    }
+}
+
+pub fn walk_expr_mut<'a, F: Fold<'a>>(folder: &mut F, e: Expr<'a>) -> Expr<'a> {
+    let (kind, loc) = e.into_parts();
+    let kind = match kind {
+        kind @ ExprType::EInt(_) => kind,
+        kind @ ExprType::EFloat(_) => kind,
+        kind @ ExprType::EId(_) => kind,
+        kind @ ExprType::EString(_) => kind,
+        ExprType::EArray(items) => {
+            ExprType::EArray(items.into_iter().map(|item| folder.fold_expr(item)).collect())
+        }
+        ExprType::EUnop(op, operand) => {
+            ExprType::EUnop(op, Box::new(folder.fold_expr(*operand)))
+        }
+        ExprType::EBinop(op, operands) => {
+            let (lhs, rhs) = *operands;
+            ExprType::EBinop(op, Box::new((folder.fold_expr(lhs), folder.fold_expr(rhs))))
+        }
+        ExprType::EVar(ty, bindings) => ExprType::EVar(
+            folder.fold_type(ty),
+            bindings
+                .into_iter()
+                .map(|(name, init)| (name, init.map(|init| folder.fold_expr(init))))
+                .collect(),
+        ),
+        ExprType::EFunc(name, flags, params, body) => ExprType::EFunc(
+            name,
+            flags,
+            params.into_iter().map(|p| folder.fold_type(p)).collect(),
+            body.into_iter().map(|stmt| folder.fold_expr(stmt)).collect(),
+        ),
+        ExprType::EClass(name, flags, fields, body) => ExprType::EClass(
+            name,
+            flags,
+            fields.into_iter().map(|f| folder.fold_type(f)).collect(),
+            body.into_iter().map(|stmt| folder.fold_expr(stmt)).collect(),
+        ),
+        ExprType::EIf(branches, else_block) => ExprType::EIf(
+            branches
+                .into_iter()
+                .map(|(cond, block)| {
+                    (
+                        folder.fold_expr(cond),
+                        block.into_iter().map(|stmt| folder.fold_expr(stmt)).collect(),
+                    )
+                })
+                .collect(),
+            else_block.map(|block| block.into_iter().map(|stmt| folder.fold_expr(stmt)).collect()),
+        ),
+    };
+
+    Expr::new(kind, loc)
+}
+
+pub fn walk_type_mut<'a, F: Fold<'a>>(folder: &mut F, t: Type<'a>) -> Type<'a> {
+    match t {
+        Type::Generic(name, args) => {
+            Type::Generic(name, args.into_iter().map(|arg| folder.fold_type(arg)).collect())
+        }
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::ast::Span;
+
+    const LOC: Span = Span { line: 1, column: 1, line_start: 0, width: 1 };
+
+    // A visitor that only overrides visit_id, to prove a single-variant
+    // pass doesn't need to hand-copy the rest of the traversal.
+    #[derive(Default)]
+    struct IdCollector(Vec<String>);
+
+    impl<'a> Visitor<'a> for IdCollector {
+        fn visit_id(&mut self, name: &str) {
+            self.0.push(name.to_string());
+        }
+    }
+
+    #[test]
+    fn single_hook_override_still_sees_nested_ids() {
+        let expr = Expr::new(
+            ExprType::EBinop(
+                Operator::Add,
+                Box::new((
+                    Expr::new(ExprType::EId("a".into()), LOC),
+                    Expr::new(ExprType::EId("b".into()), LOC),
+                )),
+            ),
+            LOC,
+        );
+
+        let mut collector = IdCollector::default();
+        collector.visit_expr(&expr);
+
+        assert_eq!(collector.0, vec!["a".to_string(), "b".to_string()]);
+    }
+
+    // A folder that only overrides fold_expr for EId, renaming identifiers,
+    // to prove the same single-variant-override story for Fold.
+    struct Renamer;
+
+    impl<'a> Fold<'a> for Renamer {
+        fn fold_expr(&mut self, e: Expr<'a>) -> Expr<'a> {
+            match e.kind() {
+                ExprType::EId(_) => Expr::new(ExprType::EId("renamed".into()), e.loc()),
+                _ => walk_expr_mut(self, e),
+            }
+        }
+    }
+
+    #[test]
+    fn fold_can_override_single_variant() {
+        let expr = Expr::new(
+            ExprType::EUnop(Operator::Not, Box::new(Expr::new(ExprType::EId("x".into()), LOC))),
+            LOC,
+        );
+
+        let renamed = Renamer.fold_expr(expr);
+
+        match renamed.kind() {
+            ExprType::EUnop(_, operand) => match operand.kind() {
+                ExprType::EId(name) => assert_eq!(name.as_ref(), "renamed"),
+                _ => panic!("expected EId"),
+            },
+            _ => panic!("expected EUnop"),
+        }
+    }
+}